@@ -1,5 +1,14 @@
 use std::borrow::Cow;
 
+use crate::TextSize;
+
+#[cfg(not(feature = "large-input"))]
+const SIZE_LIMIT_MSG: &str =
+    "We only support string up to 4GB in size, which is the maximum size of the u32.";
+#[cfg(feature = "large-input")]
+const SIZE_LIMIT_MSG: &str =
+    "We only support string up to 16EB in size, which is the maximum size of the u64.";
+
 #[derive(Debug, Clone)]
 pub struct BasicCowStr<'text> {
     inner: Cow<'text, str>,
@@ -7,17 +16,18 @@ pub struct BasicCowStr<'text> {
 
 impl<'text> BasicCowStr<'text> {
     pub fn new(inner: Cow<'text, str>) -> Self {
-        assert!(
-            u32::try_from(inner.len()).is_ok(),
-            "We only support string up to 4GB in size, which is the maximum size of the u32."
-        );
+        assert!(TextSize::try_from(inner.len()).is_ok(), "{}", SIZE_LIMIT_MSG);
         Self { inner }
     }
 
-    pub fn len(&self) -> u32 {
+    pub fn len(&self) -> TextSize {
         // We can safely do converting here because we have already asserted that
-        // the length of the string is less than or equal `u32::MAX`
-        self.inner.len() as u32
+        // the length of the string is less than or equal `TextSize::MAX`
+        self.inner.len() as TextSize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
     }
 
     pub fn as_str(&self) -> &str {
@@ -38,3 +48,26 @@ impl<'text, T: Into<Cow<'text, str>>> From<T> for BasicCowStr<'text> {
         Self::new(value.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn len_returns_the_byte_length() {
+        let s = BasicCowStr::from("héllo");
+        assert_eq!(s.len(), "héllo".len() as TextSize);
+    }
+
+    #[cfg(not(feature = "large-input"))]
+    #[test]
+    fn text_size_defaults_to_u32() {
+        assert_eq!(TextSize::MAX as u64, u32::MAX as u64);
+    }
+
+    #[cfg(feature = "large-input")]
+    #[test]
+    fn large_input_widens_text_size_to_u64() {
+        assert_eq!(TextSize::MAX, u64::MAX);
+    }
+}
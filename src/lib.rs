@@ -0,0 +1,22 @@
+mod basic_types;
+mod source_map;
+
+pub mod chunk;
+pub mod magic_string;
+pub mod span;
+
+pub use basic_types::BasicCowStr;
+pub use magic_string::MagicString;
+pub use source_map::{SourceMap, SourceMapOptions};
+
+pub type CowStr<'text> = BasicCowStr<'text>;
+
+/// The integer type used for text offsets and lengths.
+///
+/// Defaults to `u32` for cache-friendly [`index_vec::IndexVec`] storage, capping
+/// inputs at 4 GB. Enable the `large-input` feature to widen it to `u64` so the
+/// crate can process very large generated/minified bundles.
+#[cfg(not(feature = "large-input"))]
+pub type TextSize = u32;
+#[cfg(feature = "large-input")]
+pub type TextSize = u64;
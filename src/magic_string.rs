@@ -0,0 +1,223 @@
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
+
+use crate::{
+    chunk::{Chunk, ChunkIdx, ChunkVec, EditOptions},
+    span::Span,
+    CowStr, TextSize,
+};
+
+pub struct MagicString<'text> {
+    pub intro: VecDeque<CowStr<'text>>,
+    pub outro: VecDeque<CowStr<'text>>,
+    pub(crate) source: CowStr<'text>,
+    pub(crate) chunks: ChunkVec<'text>,
+    pub(crate) first_chunk_idx: Option<ChunkIdx>,
+    pub(crate) last_chunk_idx: Option<ChunkIdx>,
+    /// Maps an original text index to the chunk that starts there.
+    chunk_by_start: HashMap<TextSize, ChunkIdx>,
+    /// Maps an original text index to the chunk that ends there.
+    chunk_by_end: HashMap<TextSize, ChunkIdx>,
+}
+
+impl<'text> MagicString<'text> {
+    pub fn new(source: impl Into<CowStr<'text>>) -> Self {
+        let source = source.into();
+        let mut chunks = ChunkVec::with_capacity(1);
+        let mut chunk_by_start = HashMap::default();
+        let mut chunk_by_end = HashMap::default();
+        let (first_chunk_idx, last_chunk_idx) = if source.is_empty() {
+            (None, None)
+        } else {
+            let idx = chunks.push(Chunk::new(Span(0, source.len())));
+            chunk_by_start.insert(0, idx);
+            chunk_by_end.insert(source.len(), idx);
+            (Some(idx), Some(idx))
+        };
+        Self {
+            intro: VecDeque::default(),
+            outro: VecDeque::default(),
+            source,
+            chunks,
+            first_chunk_idx,
+            last_chunk_idx,
+            chunk_by_start,
+            chunk_by_end,
+        }
+    }
+
+    pub(crate) fn source_str(&self) -> &str {
+        self.source.as_str()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn chunk_idx_by_start(&self, start: TextSize) -> ChunkIdx {
+        self.chunk_by_start[&start]
+    }
+
+    /// Walks the chunks in generated order by following each chunk's `next` link.
+    pub(crate) fn iter_chunks(&self) -> impl Iterator<Item = &Chunk<'text>> {
+        let mut cursor = self.first_chunk_idx;
+        std::iter::from_fn(move || {
+            let idx = cursor?;
+            let chunk = &self.chunks[idx];
+            cursor = chunk.next;
+            Some(chunk)
+        })
+    }
+
+    fn chunk_idx_containing(&self, text_index: TextSize) -> ChunkIdx {
+        let mut cursor = self.first_chunk_idx;
+        while let Some(idx) = cursor {
+            let chunk = &self.chunks[idx];
+            if chunk.contains(text_index) {
+                return idx;
+            }
+            cursor = chunk.next;
+        }
+        panic!("text index {text_index} is out of bounds");
+    }
+
+    /// Ensures that a chunk boundary exists at `text_index` by splitting the
+    /// chunk that straddles it. A no-op when `text_index` already lands on a
+    /// boundary (or one of the ends of the source).
+    pub(crate) fn split_at(&mut self, text_index: TextSize) {
+        if text_index == 0
+            || self.chunk_by_start.contains_key(&text_index)
+            || self.chunk_by_end.contains_key(&text_index)
+        {
+            return;
+        }
+        let target_idx = self.chunk_idx_containing(text_index);
+        let new_chunk = self.chunks[target_idx].split(text_index);
+        let new_start = new_chunk.start();
+        let new_end = new_chunk.end();
+        let new_idx = self.chunks.push(new_chunk);
+        self.chunks[target_idx].next = Some(new_idx);
+        self.chunk_by_end.insert(text_index, target_idx);
+        self.chunk_by_start.insert(new_start, new_idx);
+        self.chunk_by_end.insert(new_end, new_idx);
+        if self.last_chunk_idx == Some(target_idx) {
+            self.last_chunk_idx = Some(new_idx);
+        }
+    }
+
+    /// Returns `true` when `text_index` falls strictly inside an edited chunk
+    /// (i.e. not on a chunk boundary), which `split_at` cannot legally cut.
+    fn is_mid_edited_chunk(&self, text_index: TextSize) -> bool {
+        if self.chunk_by_start.contains_key(&text_index)
+            || self.chunk_by_end.contains_key(&text_index)
+        {
+            return false;
+        }
+        let mut cursor = self.first_chunk_idx;
+        while let Some(idx) = cursor {
+            let chunk = &self.chunks[idx];
+            if chunk.contains(text_index) {
+                return chunk.is_edited();
+            }
+            cursor = chunk.next;
+        }
+        false
+    }
+
+    /// Deletes the `[start, end)` span and returns the original source text that
+    /// occupied it, mirroring [`String::drain`]. Any `intro` inserted before the
+    /// range and `outro` inserted after it survive the removal.
+    pub fn drain(&mut self, range: Range<TextSize>) -> CowStr<'text> {
+        let Range { start, end } = range;
+        debug_assert!(start <= end, "drain range start must not exceed end");
+        debug_assert!(end <= self.source.len(), "drain range is out of bounds");
+        debug_assert!(
+            !self.is_mid_edited_chunk(start),
+            "drain range must not start inside an already-edited chunk"
+        );
+        debug_assert!(
+            !self.is_mid_edited_chunk(end),
+            "drain range must not end inside an already-edited chunk"
+        );
+        self.split_at(start);
+        self.split_at(end);
+
+        let mut covered = Vec::new();
+        let mut cursor = self.first_chunk_idx;
+        while let Some(idx) = cursor {
+            let chunk = &self.chunks[idx];
+            if chunk.start() >= end {
+                break;
+            }
+            if chunk.start() >= start {
+                covered.push(idx);
+            }
+            cursor = chunk.next;
+        }
+
+        let mut drained = String::new();
+        for &idx in &covered {
+            drained.push_str(self.chunks[idx].span.text(self.source.as_str()));
+        }
+
+        // `edit` with `overwrite: true` clears the surrounding `intro`/`outro`,
+        // so preserve the ones that bookend the removed range.
+        let first = covered.first().copied();
+        let last = covered.last().copied();
+        let saved_intro = first.map(|idx| std::mem::take(&mut self.chunks[idx].intro));
+        let saved_outro = last.map(|idx| std::mem::take(&mut self.chunks[idx].outro));
+        for &idx in &covered {
+            self.chunks[idx].edit(
+                "".into(),
+                EditOptions {
+                    overwrite: true,
+                    store_name: false,
+                },
+            );
+        }
+        if let (Some(idx), Some(intro)) = (first, saved_intro) {
+            self.chunks[idx].intro = intro;
+        }
+        if let (Some(idx), Some(outro)) = (last, saved_outro) {
+            self.chunks[idx].outro = outro;
+        }
+
+        drained.into()
+    }
+
+    /// Deletes the `[start, end)` span, discarding the removed text. See [`MagicString::drain`].
+    pub fn remove(&mut self, range: Range<TextSize>) {
+        self.drain(range);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drain_returns_the_excised_text() {
+        let mut s = MagicString::new("hello world");
+        assert_eq!(s.drain(0..5).as_str(), "hello");
+        assert_eq!(s.drain(6..11).as_str(), "world");
+    }
+
+    #[test]
+    fn empty_drain_is_a_noop() {
+        let mut s = MagicString::new("abc");
+        assert_eq!(s.drain(1..1).as_str(), "");
+    }
+
+    #[test]
+    fn drain_preserves_surrounding_intro_and_outro() {
+        let mut s = MagicString::new("abcdef");
+        s.split_at(2);
+        s.split_at(4);
+        let covered = s.chunk_by_start[&2];
+        s.chunks[covered].append_intro("<".into());
+        s.chunks[covered].append_outro(">".into());
+
+        assert_eq!(s.drain(2..4).as_str(), "cd");
+        let chunk = &s.chunks[covered];
+        assert_eq!(chunk.edited_content.as_ref().map(|c| c.as_str()), Some(""));
+        assert_eq!(chunk.intro.front().map(|c| c.as_ref()), Some("<"));
+        assert_eq!(chunk.outro.front().map(|c| c.as_ref()), Some(">"));
+    }
+}
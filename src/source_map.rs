@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+
+use crate::magic_string::MagicString;
+
+const BASE64_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[derive(Debug, Default)]
+pub struct SourceMapOptions {
+    /// The name recorded in the `sources` field of the generated map.
+    pub source_name: String,
+}
+
+/// A [Source Map v3](https://sourcemaps.info/spec.html) document.
+#[derive(Debug)]
+pub struct SourceMap {
+    pub version: u8,
+    pub sources: Vec<String>,
+    pub names: Vec<String>,
+    pub mappings: String,
+}
+
+impl SourceMap {
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\"version\":3,\"sources\":[");
+        push_string_array(&mut out, &self.sources);
+        out.push_str("],\"names\":[");
+        push_string_array(&mut out, &self.names);
+        out.push_str("],\"mappings\":");
+        push_json_string(&mut out, &self.mappings);
+        out.push('}');
+        out
+    }
+
+    pub fn to_url(&self) -> String {
+        format!(
+            "data:application/json;charset=utf-8;base64,{}",
+            base64_encode(self.to_json().as_bytes())
+        )
+    }
+}
+
+impl<'text> MagicString<'text> {
+    pub fn generate_map(&self, opts: SourceMapOptions) -> SourceMap {
+        let source = self.source_str();
+        let line_offsets = LineOffsets::new(source);
+
+        let mut names: Vec<String> = Vec::new();
+        let mut name_indices: HashMap<String, i64> = HashMap::default();
+        let mut builder = MappingBuilder::default();
+
+        for frag in &self.intro {
+            builder.advance(frag.as_str());
+        }
+
+        for chunk in self.iter_chunks() {
+            for frag in &chunk.intro {
+                builder.advance(frag.as_str());
+            }
+
+            let (orig_line, orig_col) = line_offsets.locate(chunk.start());
+            if let Some(edited) = chunk.edited_content.as_ref() {
+                let name_index = if chunk.store_name {
+                    let name = chunk.span.text(source).to_string();
+                    let next = names.len() as i64;
+                    let idx = *name_indices.entry(name.clone()).or_insert_with(|| {
+                        names.push(name);
+                        next
+                    });
+                    Some(idx)
+                } else {
+                    None
+                };
+                builder.add_edited(edited.as_str(), 0, orig_line, orig_col, name_index);
+            } else {
+                builder.add_source(chunk.span.text(source), 0, orig_line, orig_col);
+            }
+
+            for frag in &chunk.outro {
+                builder.advance(frag.as_str());
+            }
+        }
+
+        SourceMap {
+            version: 3,
+            sources: vec![opts.source_name],
+            names,
+            mappings: builder.into_mappings(),
+        }
+    }
+}
+
+/// Tracks the original line/column at every byte offset of the source.
+struct LineOffsets {
+    /// The byte offset at which each line starts.
+    line_starts: Vec<usize>,
+}
+
+impl LineOffsets {
+    fn new(source: &str) -> Self {
+        let mut line_starts = vec![0usize];
+        for (offset, byte) in source.bytes().enumerate() {
+            if byte == b'\n' {
+                line_starts.push(offset + 1);
+            }
+        }
+        Self { line_starts }
+    }
+
+    fn locate(&self, offset: crate::TextSize) -> (i64, i64) {
+        let offset = offset as usize;
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next) => next - 1,
+        };
+        (line as i64, (offset - self.line_starts[line]) as i64)
+    }
+}
+
+struct MappingBuilder {
+    out: String,
+    /// Current generated column.
+    gen_col: i64,
+    /// Whether the next segment is the first one on the current line.
+    first_on_line: bool,
+    /// Previous segment's generated column, reset to zero on every new line.
+    prev_gen_col: i64,
+    prev_source: i64,
+    prev_orig_line: i64,
+    prev_orig_col: i64,
+    prev_name: i64,
+}
+
+impl Default for MappingBuilder {
+    fn default() -> Self {
+        Self {
+            out: String::new(),
+            gen_col: 0,
+            // The first segment of the first line must not be preceded by a `,`.
+            first_on_line: true,
+            prev_gen_col: 0,
+            prev_source: 0,
+            prev_orig_line: 0,
+            prev_orig_col: 0,
+            prev_name: 0,
+        }
+    }
+}
+
+impl MappingBuilder {
+    /// Moves the generated cursor across `content` without emitting mappings.
+    fn advance(&mut self, content: &str) {
+        for ch in content.chars() {
+            if ch == '\n' {
+                self.newline();
+            } else {
+                self.gen_col += 1;
+            }
+        }
+    }
+
+    /// Emits a mapping for an unedited chunk, advancing the original column per
+    /// character and resetting it on every embedded newline.
+    fn add_source(&mut self, content: &str, source: i64, mut orig_line: i64, orig_col: i64) {
+        self.add_segment(source, orig_line, orig_col, None);
+        for ch in content.chars() {
+            if ch == '\n' {
+                // A new generated line starts at the first column of the next
+                // original line.
+                self.newline();
+                orig_line += 1;
+                self.add_segment(source, orig_line, 0, None);
+            } else {
+                self.gen_col += 1;
+            }
+        }
+    }
+
+    /// Emits a mapping for an edited chunk. The original position is reset to
+    /// the chunk's start, and each generated line produced by the replacement
+    /// content gets its own segment pointing back at that start.
+    fn add_edited(
+        &mut self,
+        content: &str,
+        source: i64,
+        orig_line: i64,
+        orig_col: i64,
+        name: Option<i64>,
+    ) {
+        self.add_segment(source, orig_line, orig_col, name);
+        for ch in content.chars() {
+            if ch == '\n' {
+                self.newline();
+                self.add_segment(source, orig_line, orig_col, None);
+            } else {
+                self.gen_col += 1;
+            }
+        }
+    }
+
+    fn newline(&mut self) {
+        self.out.push(';');
+        self.gen_col = 0;
+        self.prev_gen_col = 0;
+        self.first_on_line = true;
+    }
+
+    fn add_segment(&mut self, source: i64, orig_line: i64, orig_col: i64, name: Option<i64>) {
+        if self.first_on_line {
+            self.first_on_line = false;
+        } else {
+            self.out.push(',');
+        }
+        encode_vlq(self.gen_col - self.prev_gen_col, &mut self.out);
+        self.prev_gen_col = self.gen_col;
+        encode_vlq(source - self.prev_source, &mut self.out);
+        self.prev_source = source;
+        encode_vlq(orig_line - self.prev_orig_line, &mut self.out);
+        self.prev_orig_line = orig_line;
+        encode_vlq(orig_col - self.prev_orig_col, &mut self.out);
+        self.prev_orig_col = orig_col;
+        if let Some(name) = name {
+            encode_vlq(name - self.prev_name, &mut self.out);
+            self.prev_name = name;
+        }
+    }
+
+    fn into_mappings(self) -> String {
+        self.out
+    }
+}
+
+fn encode_vlq(value: i64, out: &mut String) {
+    let mut vlq = if value < 0 {
+        ((-value) << 1) | 1
+    } else {
+        value << 1
+    };
+    loop {
+        let mut digit = (vlq & 0b1_1111) as usize;
+        vlq >>= 5;
+        if vlq > 0 {
+            digit |= 0b10_0000;
+        }
+        out.push(BASE64_CHARS[digit] as char);
+        if vlq == 0 {
+            break;
+        }
+    }
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b0 = chunk[0] as usize;
+        let b1 = chunk.get(1).copied().unwrap_or(0) as usize;
+        let b2 = chunk.get(2).copied().unwrap_or(0) as usize;
+        out.push(BASE64_CHARS[b0 >> 2] as char);
+        out.push(BASE64_CHARS[((b0 & 0b11) << 4) | (b1 >> 4)] as char);
+        if chunk.len() > 1 {
+            out.push(BASE64_CHARS[((b1 & 0b1111) << 2) | (b2 >> 6)] as char);
+        } else {
+            out.push('=');
+        }
+        if chunk.len() > 2 {
+            out.push(BASE64_CHARS[b2 & 0b11_1111] as char);
+        } else {
+            out.push('=');
+        }
+    }
+    out
+}
+
+fn push_string_array(out: &mut String, items: &[String]) {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        push_json_string(out, item);
+    }
+}
+
+fn push_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MagicString;
+
+    #[test]
+    fn vlq_encodes_signed_deltas() {
+        let mut out = String::new();
+        encode_vlq(0, &mut out);
+        encode_vlq(1, &mut out);
+        encode_vlq(-1, &mut out);
+        encode_vlq(16, &mut out);
+        assert_eq!(out, "ACDgB");
+    }
+
+    #[test]
+    fn unedited_source_has_no_leading_comma() {
+        let s = MagicString::new("abc");
+        let map = s.generate_map(SourceMapOptions::default());
+        // A single unedited chunk: one segment on the first line, all deltas zero.
+        assert_eq!(map.mappings, "AAAA");
+    }
+
+    #[test]
+    fn edited_chunk_emits_a_segment_per_generated_line() {
+        let mut s = MagicString::new("abcdef");
+        s.split_at(2);
+        s.split_at(4);
+        let idx = s.chunk_idx_by_start(2);
+        s.chunks[idx].edit(
+            "X\nY".into(),
+            crate::chunk::EditOptions {
+                overwrite: true,
+                store_name: false,
+            },
+        );
+        let map = s.generate_map(SourceMapOptions::default());
+        // "ab" → first line segment, edited "X\nY" → a segment on each of its two
+        // lines mapping back to the chunk start, then "ef" on the last line.
+        assert_eq!(map.mappings, "AAAA,EAAE;AAAA,CAAE");
+    }
+
+    #[test]
+    fn to_json_is_a_v3_document() {
+        let s = MagicString::new("abc");
+        let map = s.generate_map(SourceMapOptions {
+            source_name: "input.js".into(),
+        });
+        assert_eq!(
+            map.to_json(),
+            "{\"version\":3,\"sources\":[\"input.js\"],\"names\":[],\"mappings\":\"AAAA\"}"
+        );
+        assert!(map.to_url().starts_with("data:application/json;charset=utf-8;base64,"));
+    }
+}
@@ -0,0 +1,18 @@
+use crate::TextSize;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Span(pub TextSize, pub TextSize);
+
+impl Span {
+    pub fn start(&self) -> TextSize {
+        self.0
+    }
+
+    pub fn end(&self) -> TextSize {
+        self.1
+    }
+
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.0 as usize..self.1 as usize]
+    }
+}